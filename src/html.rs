@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
 
 use askama::Template;
+use syntect::highlighting::{Theme, ThemeSet};
 
 use crate::{
     context::all_matched_lines_filled,
@@ -11,9 +13,33 @@ use crate::{
     syntax::{AtomKind, MatchKind, MatchedPos, TokenKind},
 };
 
-type StyledLine = Vec<(String, Vec<&'static str>)>;
+/// A run of text together with the CSS classes it carries and, when a
+/// syntax theme is active, the resolved inline `color:#rrggbb` style.
+type StyledSpan = (String, Vec<&'static str>, Option<String>);
+type StyledLine = Vec<StyledSpan>;
 type NumberedLine = (LineNumber, StyledLine);
 
+/// The rendered state of a single file's diff, shared by the per-file
+/// [`SummaryTemplate`] and the aggregating [`ReportTemplate`].
+struct FileSummary {
+    display_path: String,
+    /// Slug used for the table-of-contents anchor, e.g. `file-0-src-foo-rs`.
+    anchor: String,
+    /// Number of lines that differ, summed across both sides.
+    changed_lines: usize,
+    paired_lines: Vec<(Option<NumberedLine>, Option<NumberedLine>)>,
+    lhs_lines_with_novel: HashSet<LineNumber>,
+    rhs_lines_with_novel: HashSet<LineNumber>,
+    /// Moved lines mapped to their palette index, so the row background reads
+    /// as "moved" rather than the plain novel added/removed colour.
+    lhs_moved: HashMap<LineNumber, usize>,
+    rhs_moved: HashMap<LineNumber, usize>,
+    /// Lines the caller wants pre-highlighted (rendered with the `highlighted`
+    /// background class and deep-linkable via a `#L-<side>-<n>` fragment, which
+    /// the aggregate report prefixes with the file's anchor).
+    highlight_lines: HashSet<LineNumber>,
+}
+
 #[derive(Template)]
 #[template(path = "summary.html")]
 struct SummaryTemplate {
@@ -21,28 +47,207 @@ struct SummaryTemplate {
     paired_lines: Vec<(Option<NumberedLine>, Option<NumberedLine>)>,
     lhs_lines_with_novel: HashSet<LineNumber>,
     rhs_lines_with_novel: HashSet<LineNumber>,
+    lhs_moved: HashMap<LineNumber, usize>,
+    rhs_moved: HashMap<LineNumber, usize>,
+    highlight_lines: HashSet<LineNumber>,
+}
+
+/// A self-contained document aggregating many files: an embedded stylesheet, a
+/// navigation sidebar, and one collapsible `<details>` section per file.
+#[derive(Template)]
+#[template(path = "report.html")]
+struct ReportTemplate {
+    files: Vec<FileSummary>,
+}
+
+/// Runs shorter than this are too small to confidently call a moved block.
+const MOVED_MIN_LINES: usize = 2;
+
+/// Background classes cycled through when colouring moved blocks.
+/// Both sides of a pair share the same index so the eye can follow a block
+/// from where it was deleted to where it was reinserted.
+const MOVED_LHS_CLASSES: [&str; 8] = [
+    "moved-lhs-0",
+    "moved-lhs-1",
+    "moved-lhs-2",
+    "moved-lhs-3",
+    "moved-lhs-4",
+    "moved-lhs-5",
+    "moved-lhs-6",
+    "moved-lhs-7",
+];
+const MOVED_RHS_CLASSES: [&str; 8] = [
+    "moved-rhs-0",
+    "moved-rhs-1",
+    "moved-rhs-2",
+    "moved-rhs-3",
+    "moved-rhs-4",
+    "moved-rhs-5",
+    "moved-rhs-6",
+    "moved-rhs-7",
+];
+
+/// A contiguous run of novel lines on one side of the diff.
+struct NovelRun {
+    lines: Vec<LineNumber>,
+    key: String,
+}
+
+/// Gather maximal runs of consecutive novel lines and, for runs of at least
+/// [`MOVED_MIN_LINES`], record a whitespace-normalized key used for matching.
+fn novel_runs(src_lines: &[&str], novel: &HashSet<LineNumber>) -> Vec<NovelRun> {
+    let mut runs = vec![];
+    let mut current: Vec<LineNumber> = vec![];
+
+    let mut flush = |current: &mut Vec<LineNumber>, runs: &mut Vec<NovelRun>| {
+        if current.len() >= MOVED_MIN_LINES {
+            let key = current
+                .iter()
+                .map(|ln| src_lines[ln.0].trim())
+                .collect::<Vec<_>>()
+                .join("\n");
+            // A run of blank lines normalizes to an empty key; pairing those
+            // would colour matching whitespace gaps as a moved block.
+            if !key.trim().is_empty() {
+                runs.push(NovelRun {
+                    lines: current.clone(),
+                    key,
+                });
+            }
+        }
+        current.clear();
+    };
+
+    for i in 0..src_lines.len() {
+        let ln = LineNumber(i);
+        if novel.contains(&ln) {
+            current.push(ln);
+        } else {
+            flush(&mut current, &mut runs);
+        }
+    }
+    flush(&mut current, &mut runs);
+
+    runs
+}
+
+/// Pair up LHS and RHS novel runs that have identical normalized content and
+/// assign each pair a palette index. Pairing is strictly one-to-one: a key is
+/// only matched when exactly one run on each side carries it, which keeps the
+/// colour assignment unambiguous. Longer blocks get the lower indices so the
+/// most prominent moves are coloured first.
+fn detect_moved_blocks(
+    lhs_lines: &[&str],
+    rhs_lines: &[&str],
+    lhs_novel: &HashSet<LineNumber>,
+    rhs_novel: &HashSet<LineNumber>,
+) -> (HashMap<LineNumber, usize>, HashMap<LineNumber, usize>) {
+    let lhs_runs = novel_runs(lhs_lines, lhs_novel);
+    let rhs_runs = novel_runs(rhs_lines, rhs_novel);
+
+    let mut lhs_by_key: HashMap<&str, Vec<&NovelRun>> = HashMap::new();
+    for run in &lhs_runs {
+        lhs_by_key.entry(&run.key).or_default().push(run);
+    }
+    let mut rhs_by_key: HashMap<&str, Vec<&NovelRun>> = HashMap::new();
+    for run in &rhs_runs {
+        rhs_by_key.entry(&run.key).or_default().push(run);
+    }
+
+    let mut pairs: Vec<(&NovelRun, &NovelRun)> = vec![];
+    for (key, lhs_group) in &lhs_by_key {
+        if let Some(rhs_group) = rhs_by_key.get(key) {
+            if lhs_group.len() == 1 && rhs_group.len() == 1 {
+                pairs.push((lhs_group[0], rhs_group[0]));
+            }
+        }
+    }
+    // Sort longest-first, breaking ties deterministically by the LHS run's
+    // first line number and then by key, so the palette assignment is
+    // reproducible regardless of the unordered HashMap iteration above.
+    pairs.sort_by(|a, b| {
+        b.0.lines
+            .len()
+            .cmp(&a.0.lines.len())
+            .then_with(|| a.0.lines[0].cmp(&b.0.lines[0]))
+            .then_with(|| a.0.key.cmp(&b.0.key))
+    });
+
+    let mut lhs_moved = HashMap::new();
+    let mut rhs_moved = HashMap::new();
+    for (i, (lhs_run, rhs_run)) in pairs.iter().enumerate() {
+        let palette = i % MOVED_LHS_CLASSES.len();
+        for ln in &lhs_run.lines {
+            lhs_moved.insert(*ln, palette);
+        }
+        for ln in &rhs_run.lines {
+            rhs_moved.insert(*ln, palette);
+        }
+    }
+
+    (lhs_moved, rhs_moved)
+}
+
+/// The TextMate scope that best describes `highlight`. Kept deliberately
+/// coarse: difftastic only distinguishes a handful of atom kinds, so we map
+/// each to the conventional scope a `.tmTheme` is likely to define.
+fn scope_for(highlight: TokenKind) -> &'static str {
+    match highlight {
+        TokenKind::Atom(kind) => match kind {
+            AtomKind::Normal => "source",
+            AtomKind::String => "string",
+            AtomKind::Type => "storage.type",
+            AtomKind::Comment => "comment",
+            AtomKind::Keyword => "keyword",
+        },
+        _ => "source",
+    }
+}
+
+/// Load a theme by name from the set bundled with syntect, falling back to a
+/// light default when the requested theme is unknown.
+fn load_theme(theme_name: &str) -> Theme {
+    let mut theme_set = ThemeSet::load_defaults();
+    theme_set
+        .themes
+        .remove(theme_name)
+        .unwrap_or_else(|| theme_set.themes.remove("InspiredGitHub").unwrap())
+}
+
+/// Resolve the foreground color a theme assigns to `scope`, formatted as an
+/// inline `color:#rrggbb` declaration suitable for a `style` attribute.
+fn color_for(theme: &Theme, scope: &str) -> Option<String> {
+    let scope: syntect::parsing::Scope = scope.parse().ok()?;
+    let fg = theme
+        .scopes
+        .iter()
+        .filter(|item| item.scope.does_match(&[scope]).is_some())
+        .filter_map(|item| item.style.foreground)
+        .last()?;
+    Some(format!("color:#{:02x}{:02x}{:02x}", fg.r, fg.g, fg.b))
 }
 
 fn apply_line(
     line: &str,
-    styles: &[(SingleLineSpan, Vec<&'static str>)],
-) -> Vec<(String, Vec<&'static str>)> {
+    styles: &[(SingleLineSpan, Vec<&'static str>, Option<String>)],
+) -> StyledLine {
     let mut offset = 0;
     let mut res = vec![];
 
-    for (span, classes) in styles {
+    for (span, classes, color) in styles {
         if offset < span.start_col {
-            res.push((line[offset..span.start_col].to_owned(), vec![]));
+            res.push((line[offset..span.start_col].to_owned(), vec![], None));
         }
 
         res.push((
             line[span.start_col..span.end_col].to_owned(),
             classes.clone(),
+            color.clone(),
         ));
         offset = span.end_col;
     }
     if offset < codepoint_len(line) {
-        res.push((line[offset..].to_owned(), vec![]));
+        res.push((line[offset..].to_owned(), vec![], None));
     }
 
     res
@@ -51,7 +256,9 @@ fn apply_line(
 fn apply_styles(
     is_lhs: bool,
     mps: &[MatchedPos],
-) -> HashMap<LineNumber, Vec<(SingleLineSpan, Vec<&'static str>)>> {
+    theme: &Theme,
+    moved: &HashMap<LineNumber, usize>,
+) -> HashMap<LineNumber, Vec<(SingleLineSpan, Vec<&'static str>, Option<String>)>> {
     let mut line_styles = HashMap::new();
     for mp in mps {
         let line_pos = mp.pos;
@@ -59,7 +266,13 @@ fn apply_styles(
         match mp.kind {
             MatchKind::Novel { .. }
             | MatchKind::NovelWord { .. } => {
-                span_classes.push(if is_lhs { "novel-lhs" } else { "novel-rhs" });
+                // A line only becomes "moved" when its whole run matched the
+                // other side; otherwise it stays a plain novel highlight.
+                match moved.get(&line_pos.line) {
+                    Some(&palette) if is_lhs => span_classes.push(MOVED_LHS_CLASSES[palette]),
+                    Some(&palette) => span_classes.push(MOVED_RHS_CLASSES[palette]),
+                    None => span_classes.push(if is_lhs { "novel-lhs" } else { "novel-rhs" }),
+                }
             }
             MatchKind::UnchangedToken { .. } => {}
             MatchKind::NovelLinePart { .. } => {
@@ -75,39 +288,54 @@ fn apply_styles(
             MatchKind::NovelWord { highlight } => highlight,
         };
 
-        match highlight {
-            TokenKind::Atom(kind) => match kind {
-                AtomKind::Normal => {}
-                AtomKind::String => span_classes.push("pl-s"),
-                AtomKind::Type => span_classes.push("pl-k"),
-                AtomKind::Comment => span_classes.push("pl-c"),
-                AtomKind::Keyword => span_classes.push("pl-k"),
-            },
-            _ => {}
-        }
+        // The syntax foreground colour comes from the theme; the novel
+        // highlight background is composited on top via the CSS classes above.
+        let color = color_for(theme, scope_for(highlight));
 
         let line_classes = line_styles.entry(line_pos.line).or_insert_with(Vec::new);
-        line_classes.push((line_pos, span_classes));
+        line_classes.push((line_pos, span_classes, color));
     }
 
     line_styles
 }
 
-pub fn print(
+/// Turn a non-URL-safe `display_path` into a stable anchor slug.
+fn anchor_for(display_path: &str, index: usize) -> String {
+    let slug: String = display_path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("file-{}-{}", index, slug)
+}
+
+/// Compute the rendered [`FileSummary`] for a single file's diff, without
+/// emitting anything. Shared by the single-file and aggregate entry points.
+#[allow(clippy::too_many_arguments)]
+fn summarize(
     hunks: &[Hunk],
     display_path: &str,
     lhs_src: &str,
     rhs_src: &str,
     lhs_mps: &[MatchedPos],
     rhs_mps: &[MatchedPos],
-) {
+    theme: &Theme,
+    highlight_lines: &HashSet<LineNumber>,
+    index: usize,
+) -> FileSummary {
     let lhs_lines = split_on_newlines(lhs_src);
     let rhs_lines = split_on_newlines(rhs_src);
-    let lhs_line_styles = apply_styles(true, lhs_mps);
-    let rhs_line_styles = apply_styles(false, rhs_mps);
-    let empty_styles = vec![];
 
     let (lhs_lines_with_novel, rhs_lines_with_novel) = lines_with_novel(lhs_mps, rhs_mps);
+    let (lhs_moved, rhs_moved) = detect_moved_blocks(
+        &lhs_lines,
+        &rhs_lines,
+        &lhs_lines_with_novel,
+        &rhs_lines_with_novel,
+    );
+
+    let lhs_line_styles = apply_styles(true, lhs_mps, theme, &lhs_moved);
+    let rhs_line_styles = apply_styles(false, rhs_mps, theme, &rhs_moved);
+    let empty_styles = vec![];
 
     let matched_lines = all_matched_lines_filled(lhs_mps, rhs_mps);
 
@@ -137,11 +365,365 @@ pub fn print(
         }
     }
 
-    let template = SummaryTemplate {
+    let changed_lines = lhs_lines_with_novel.len() + rhs_lines_with_novel.len();
+
+    FileSummary {
         display_path: display_path.into(),
+        anchor: anchor_for(display_path, index),
+        changed_lines,
         paired_lines,
         lhs_lines_with_novel,
         rhs_lines_with_novel,
+        lhs_moved,
+        rhs_moved,
+        highlight_lines: highlight_lines.clone(),
+    }
+}
+
+/// An output format for a diff. Implementors consume the raw hunks/sources and
+/// are responsible for building the shared [`FileSummary`] intermediate
+/// representation (via [`summarize`]) and serializing it to `out`. This keeps
+/// the class-mapping in [`apply_styles`] computed once, regardless of format.
+pub trait Emitter {
+    #[allow(clippy::too_many_arguments)]
+    fn emit(
+        &self,
+        out: &mut dyn Write,
+        hunks: &[Hunk],
+        display_path: &str,
+        lhs_src: &str,
+        rhs_src: &str,
+        lhs_mps: &[MatchedPos],
+        rhs_mps: &[MatchedPos],
+    ) -> io::Result<()>;
+}
+
+/// Emits the GitHub-style side-by-side HTML summary.
+pub struct HtmlEmitter {
+    pub theme_name: String,
+    /// Lines to pre-highlight; empty for the default "nothing highlighted".
+    pub highlight_lines: HashSet<LineNumber>,
+}
+
+impl Emitter for HtmlEmitter {
+    fn emit(
+        &self,
+        out: &mut dyn Write,
+        hunks: &[Hunk],
+        display_path: &str,
+        lhs_src: &str,
+        rhs_src: &str,
+        lhs_mps: &[MatchedPos],
+        rhs_mps: &[MatchedPos],
+    ) -> io::Result<()> {
+        let theme = load_theme(&self.theme_name);
+        let summary = summarize(
+            hunks,
+            display_path,
+            lhs_src,
+            rhs_src,
+            lhs_mps,
+            rhs_mps,
+            &theme,
+            &self.highlight_lines,
+            0,
+        );
+
+        let template = SummaryTemplate {
+            display_path: summary.display_path,
+            paired_lines: summary.paired_lines,
+            lhs_lines_with_novel: summary.lhs_lines_with_novel,
+            rhs_lines_with_novel: summary.rhs_lines_with_novel,
+            lhs_moved: summary.lhs_moved,
+            rhs_moved: summary.rhs_moved,
+            highlight_lines: summary.highlight_lines,
+        };
+        writeln!(out, "{}", template.render().expect("valid template"))
+    }
+}
+
+/// Emits the same styled-line IR as a structured JSON document, so downstream
+/// tools can consume difftastic's highlighting without parsing HTML.
+pub struct JsonEmitter {
+    pub theme_name: String,
+    pub highlight_lines: HashSet<LineNumber>,
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(
+        &self,
+        out: &mut dyn Write,
+        hunks: &[Hunk],
+        display_path: &str,
+        lhs_src: &str,
+        rhs_src: &str,
+        lhs_mps: &[MatchedPos],
+        rhs_mps: &[MatchedPos],
+    ) -> io::Result<()> {
+        let theme = load_theme(&self.theme_name);
+        let summary = summarize(
+            hunks,
+            display_path,
+            lhs_src,
+            rhs_src,
+            lhs_mps,
+            rhs_mps,
+            &theme,
+            &self.highlight_lines,
+            0,
+        );
+
+        // Build a plain JSON value from the shared IR rather than deriving
+        // Serialize on the syntax types, which live in other modules.
+        let numbered = |nl: &Option<NumberedLine>| -> serde_json::Value {
+            match nl {
+                None => serde_json::Value::Null,
+                Some((ln, spans)) => {
+                    let spans: Vec<_> = spans
+                        .iter()
+                        .map(|(text, classes, color)| {
+                            serde_json::json!({
+                                "text": text,
+                                "classes": classes,
+                                "color": color,
+                            })
+                        })
+                        .collect();
+                    serde_json::json!({ "line": ln.0, "spans": spans })
+                }
+            }
+        };
+
+        let lines: Vec<_> = summary
+            .paired_lines
+            .iter()
+            .map(|(lhs, rhs)| serde_json::json!({ "lhs": numbered(lhs), "rhs": numbered(rhs) }))
+            .collect();
+
+        let doc = serde_json::json!({
+            "display_path": summary.display_path,
+            "changed_lines": summary.changed_lines,
+            "lines": lines,
+        });
+        write!(out, "{}", doc)
+    }
+}
+
+pub fn print(
+    hunks: &[Hunk],
+    display_path: &str,
+    lhs_src: &str,
+    rhs_src: &str,
+    lhs_mps: &[MatchedPos],
+    rhs_mps: &[MatchedPos],
+    theme_name: &str,
+    highlight_lines: &HashSet<LineNumber>,
+) {
+    let emitter = HtmlEmitter {
+        theme_name: theme_name.to_owned(),
+        highlight_lines: highlight_lines.clone(),
+    };
+    emitter
+        .emit(
+            &mut io::stdout().lock(),
+            hunks,
+            display_path,
+            lhs_src,
+            rhs_src,
+            lhs_mps,
+            rhs_mps,
+        )
+        .expect("writing to stdout");
+}
+
+/// Render a single file's diff as structured JSON on stdout, the counterpart
+/// to [`print`] for tools consuming difftastic's highlighting programmatically.
+pub fn print_json(
+    hunks: &[Hunk],
+    display_path: &str,
+    lhs_src: &str,
+    rhs_src: &str,
+    lhs_mps: &[MatchedPos],
+    rhs_mps: &[MatchedPos],
+    theme_name: &str,
+    highlight_lines: &HashSet<LineNumber>,
+) {
+    let emitter = JsonEmitter {
+        theme_name: theme_name.to_owned(),
+        highlight_lines: highlight_lines.clone(),
     };
+    emitter
+        .emit(
+            &mut io::stdout().lock(),
+            hunks,
+            display_path,
+            lhs_src,
+            rhs_src,
+            lhs_mps,
+            rhs_mps,
+        )
+        .expect("writing to stdout");
+}
+
+/// A single file's worth of inputs to [`print_report`].
+pub struct ReportFile<'a> {
+    pub hunks: &'a [Hunk],
+    pub display_path: &'a str,
+    pub lhs_src: &'a str,
+    pub rhs_src: &'a str,
+    pub lhs_mps: &'a [MatchedPos],
+    pub rhs_mps: &'a [MatchedPos],
+    pub highlight_lines: &'a HashSet<LineNumber>,
+}
+
+/// Render every file into one self-contained HTML document with a navigation
+/// index, suitable for publishing as a single shareable artifact.
+pub fn print_report(files: &[ReportFile], theme_name: &str) {
+    // Parse the theme once rather than re-loading the default set per file.
+    let theme = load_theme(theme_name);
+    let summaries = files
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            summarize(
+                f.hunks,
+                f.display_path,
+                f.lhs_src,
+                f.rhs_src,
+                f.lhs_mps,
+                f.rhs_mps,
+                &theme,
+                f.highlight_lines,
+                i,
+            )
+        })
+        .collect();
+
+    let template = ReportTemplate { files: summaries };
     println!("{}", template.render().unwrap());
-}
\ No newline at end of file
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syntect::highlighting::Theme;
+
+    #[test]
+    fn test_scope_for_atom_kinds() {
+        assert_eq!(scope_for(TokenKind::Atom(AtomKind::String)), "string");
+        assert_eq!(scope_for(TokenKind::Atom(AtomKind::Keyword)), "keyword");
+        assert_eq!(scope_for(TokenKind::Atom(AtomKind::Comment)), "comment");
+        assert_eq!(scope_for(TokenKind::Atom(AtomKind::Type)), "storage.type");
+        assert_eq!(scope_for(TokenKind::Atom(AtomKind::Normal)), "source");
+    }
+
+    #[test]
+    fn test_color_for_unstyled_scope_is_none() {
+        // A theme with no scope settings cannot colour anything.
+        let theme = Theme::default();
+        assert_eq!(color_for(&theme, "keyword"), None);
+    }
+
+    fn novel_set(lines: &[usize]) -> HashSet<LineNumber> {
+        lines.iter().map(|n| LineNumber(*n)).collect()
+    }
+
+    #[test]
+    fn test_moved_block_paired_one_to_one() {
+        let lhs = vec!["fn moved() {", "    body();", "}", "keep();"];
+        let rhs = vec!["keep();", "fn moved() {", "    body();", "}"];
+        let lhs_novel = novel_set(&[0, 1, 2]);
+        let rhs_novel = novel_set(&[1, 2, 3]);
+
+        let (lhs_moved, rhs_moved) =
+            detect_moved_blocks(&lhs, &rhs, &lhs_novel, &rhs_novel);
+
+        assert_eq!(lhs_moved.get(&LineNumber(0)), Some(&0));
+        assert_eq!(lhs_moved.get(&LineNumber(2)), Some(&0));
+        assert_eq!(rhs_moved.get(&LineNumber(1)), Some(&0));
+        assert_eq!(rhs_moved.get(&LineNumber(3)), Some(&0));
+    }
+
+    #[test]
+    fn test_moved_block_ignores_short_runs() {
+        // A single novel line is below MOVED_MIN_LINES and is never moved.
+        let lhs = vec!["solo();", "keep();"];
+        let rhs = vec!["keep();", "solo();"];
+        let lhs_novel = novel_set(&[0]);
+        let rhs_novel = novel_set(&[1]);
+
+        let (lhs_moved, rhs_moved) =
+            detect_moved_blocks(&lhs, &rhs, &lhs_novel, &rhs_novel);
+
+        assert!(lhs_moved.is_empty());
+        assert!(rhs_moved.is_empty());
+    }
+
+    #[test]
+    fn test_moved_block_requires_strict_one_to_one() {
+        // The same block appears twice on the RHS, so the match is ambiguous
+        // and must not be coloured.
+        let lhs = vec!["a();", "b();"];
+        let rhs = vec!["a();", "b();", "x();", "a();", "b();"];
+        let lhs_novel = novel_set(&[0, 1]);
+        let rhs_novel = novel_set(&[0, 1, 3, 4]);
+
+        let (lhs_moved, rhs_moved) =
+            detect_moved_blocks(&lhs, &rhs, &lhs_novel, &rhs_novel);
+
+        assert!(lhs_moved.is_empty());
+        assert!(rhs_moved.is_empty());
+    }
+
+    #[test]
+    fn test_moved_block_ignores_blank_runs() {
+        // Equal-length blank-line gaps on each side must not pair.
+        let lhs = vec!["", "  ", "a();"];
+        let rhs = vec!["a();", "", "  "];
+        let lhs_novel = novel_set(&[0, 1]);
+        let rhs_novel = novel_set(&[1, 2]);
+
+        let (lhs_moved, rhs_moved) =
+            detect_moved_blocks(&lhs, &rhs, &lhs_novel, &rhs_novel);
+
+        assert!(lhs_moved.is_empty());
+        assert!(rhs_moved.is_empty());
+    }
+
+    #[test]
+    fn test_moved_block_palette_is_deterministic() {
+        // Two equal-length moved blocks: the one starting earlier on the LHS
+        // must always take palette index 0.
+        let lhs = vec!["a1();", "a2();", "keep();", "b1();", "b2();"];
+        let rhs = vec!["b1();", "b2();", "keep();", "a1();", "a2();"];
+        let lhs_novel = novel_set(&[0, 1, 3, 4]);
+        let rhs_novel = novel_set(&[0, 1, 3, 4]);
+
+        let (lhs_moved, _) = detect_moved_blocks(&lhs, &rhs, &lhs_novel, &rhs_novel);
+
+        assert_eq!(lhs_moved.get(&LineNumber(0)), Some(&0));
+        assert_eq!(lhs_moved.get(&LineNumber(3)), Some(&1));
+    }
+
+    #[test]
+    fn test_anchor_for_slugs_non_alphanumeric() {
+        assert_eq!(anchor_for("src/foo.rs", 0), "file-0-src-foo-rs");
+        assert_eq!(anchor_for("a b", 3), "file-3-a-b");
+    }
+
+    #[test]
+    fn test_json_emitter_emits_display_path() {
+        let emitter = JsonEmitter {
+            theme_name: "InspiredGitHub".into(),
+            highlight_lines: HashSet::new(),
+        };
+        let mut out = Vec::new();
+        emitter
+            .emit(&mut out, &[], "src/foo.rs", "", "", &[], &[])
+            .unwrap();
+        let rendered = String::from_utf8(out).unwrap();
+        assert!(rendered.contains("\"display_path\":\"src/foo.rs\""));
+    }
+}